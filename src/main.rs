@@ -1,75 +1,116 @@
-use std::collections::HashMap;
 use std::fs::{File};
 use std::io::Read;
-use std::{env, fs};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::fs;
+use clap::{Parser, Subcommand, ValueEnum};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use log::{info, warn, LevelFilter, Metadata, Record};
 use regex::Regex;
 
 
-#[derive(Debug)]
+/// dumb-code-analyzer: scan Spring controllers and report their HTTP endpoints.
+///
+/// NOTE: options use clap's GNU-style double-dash flags (`--mapdir`, `--format`, ...),
+/// which differs from the single-dash (`-mapdir`) syntax the original hand-rolled parser
+/// accepted. This is an intentional, documented break from the pre-clap invocation syntax.
+#[derive(Parser)]
+#[command(name = "dumb-code-analyzer", about, long_about = None)]
 struct AppArguments {
-    arguments : HashMap<String, String>,
-    exec_path : String,
-    parameters : Vec<String>,
-}
+    /// directory to map and scan for `*Controller.java` files
+    #[arg(long)]
+    mapdir : String,
 
-impl AppArguments {
-    fn new() -> AppArguments {
-        // map that will store  the application arguments 
-        let mut args = HashMap::<String, String>::new();
+    /// output format for the result tree
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format : OutputFormat,
 
-        let mut params : Vec<String> = Vec::new();
+    /// minimum log level emitted on stderr
+    #[arg(long, value_enum, default_value_t = LogLevel::Info)]
+    loglevel : LogLevel,
 
-        // store the last iterated value from the application arguments
-        let mut last_arg = String::new();
+    /// number of worker threads, defaults to the available parallelism of the host
+    #[arg(long)]
+    threads : Option<usize>,
 
-        // store the app execution path
-        let mut exec_path = String::new();
+    /// glob patterns to include; when given, only matching files are scanned (e.g. `*Controller.java`)
+    #[arg(long)]
+    include : Vec<String>,
 
-        enum Contexts {
-            ExecutionPath,
-            ArgumentName,
-            ArgumentValue,
-            Parameter,
-        }
-        let mut cur_context = Contexts::ExecutionPath;
+    /// glob patterns to exclude from the walk (e.g. `**/generated/**`)
+    #[arg(long)]
+    exclude : Vec<String>,
 
-        // iterate over each application argument
-        let app_args : Vec<String> = env::args().collect();
-        for app_arg in app_args {
+    /// regex the file name must match to be scanned
+    #[arg(long, default_value = r"^.*Controller\.java$")]
+    pattern : String,
 
-            // check if it is a special parameter
-            if let Contexts::Parameter = cur_context {
-                if app_arg.starts_with("-") {
-                    cur_context = Contexts::ArgumentName;
-                }
-            }
+    #[command(subcommand)]
+    command : Option<Command>,
+}
 
-            // add the value depending on the current context
-            match cur_context {
-                Contexts::ExecutionPath => {
-                    exec_path = app_arg;
-                    cur_context = Contexts::Parameter;
-                },
-                Contexts::ArgumentName => {
-                    last_arg = app_arg.chars().skip(1).collect();
-                    cur_context = Contexts::ArgumentValue;
-                },
-                Contexts::ArgumentValue => {
-                    args.insert(String::from(&last_arg), app_arg);
-                    cur_context = Contexts::Parameter;
-                },
-                Contexts::Parameter => {
-                    params.push(app_arg);
-                    cur_context = Contexts::Parameter;
-                }
-            }
+/// optional subcommands; when none is given the analyzer behaves as `scan`
+#[derive(Subcommand)]
+enum Command {
+    /// scan the mapped directory and print the endpoints (default)
+    Scan,
+    /// list the HTTP verbs the analyzer understands
+    ListVerbs,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Diagnostic,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn to_level_filter(self) -> LevelFilter {
+        match self {
+            Self::Trace => LevelFilter::Trace,
+            Self::Debug => LevelFilter::Debug,
+            Self::Info => LevelFilter::Info,
+            Self::Warn => LevelFilter::Warn,
+            Self::Error => LevelFilter::Error,
         }
+    }
+}
+
+// a minimal stderr logger; the CLI `--loglevel` value sets the global max level
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata : &Metadata) -> bool {
+        // honor the global max level set from `--loglevel`
+        metadata.level() <= log::max_level()
+    }
 
-        // return 
-        AppArguments { arguments: args, exec_path: exec_path, parameters: params }
+    fn log(&self, record : &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
     }
+
+    fn flush(&self) {}
 }
 
+static LOGGER : StderrLogger = StderrLogger;
+
+#[derive(Clone, Copy)]
 enum HttpVerbs {
     Get,
     Post,
@@ -96,6 +137,253 @@ impl HttpVerbs {
 
         }
     }
+
+    // every verb the analyzer can recognise, in declaration order
+    fn all() -> [HttpVerbs; 8] {
+        [
+            Self::Get,
+            Self::Post,
+            Self::Put,
+            Self::Patch,
+            Self::Delete,
+            Self::Options,
+            Self::Head,
+            Self::Any,
+        ]
+    }
+
+    // default verb implied by a mapping annotation name, e.g. `@GetMapping` -> GET,
+    // `@RequestMapping` -> ANY (unless a `method =` attribute narrows it later).
+    // returns `None` for annotations we don't recognise (e.g. `@MessageMapping`) so the
+    // scanner can skip them rather than crashing on valid Spring source.
+    fn from_annotation(annotation : &str) -> Option<HttpVerbs> {
+        match annotation {
+            "@RequestMapping" => Some(Self::Any),
+            "@DeleteMapping" => Some(Self::Delete),
+            "@GetMapping" => Some(Self::Get),
+            "@HeadMapping" => Some(Self::Head),
+            "@OptionsMapping" => Some(Self::Options),
+            "@PatchMapping" => Some(Self::Patch),
+            "@PostMapping" => Some(Self::Post),
+            "@PutMapping" => Some(Self::Put),
+            _ => None,
+        }
+    }
+
+    // verb named by a `method = RequestMethod.POST` attribute value, matched on the trailing name
+    fn from_request_method(value : &str) -> Option<HttpVerbs> {
+        match value.rsplit('.').next().unwrap_or(value) {
+            "GET" => Some(Self::Get),
+            "POST" => Some(Self::Post),
+            "PUT" => Some(Self::Put),
+            "PATCH" => Some(Self::Patch),
+            "DELETE" => Some(Self::Delete),
+            "OPTIONS" => Some(Self::Options),
+            "HEAD" => Some(Self::Head),
+            _ => None,
+        }
+    }
+}
+
+// a single path literal extracted from a mapping annotation, with its char span in the source line
+struct PathSpan {
+    path : String,
+    start : usize,
+    len : usize,
+}
+
+// the outcome of parsing one mapping annotation line: the resolved verb and one span per path
+// (the array form `value = {"/a", "/b"}` yields several)
+struct ParsedMapping {
+    http_verb : HttpVerbs,
+    paths : Vec<PathSpan>,
+}
+
+// does this line carry a mapping annotation we should parse?
+fn is_mapping_line(line : &str, endpoint_regex : &Regex) -> bool {
+    endpoint_regex.is_match(line) && line.trim().starts_with('@')
+}
+
+// does this line declare the annotated type? Spring controllers are usually classes, but
+// interface-, record- and enum-based controllers carry a class-level base path just the same.
+fn is_type_declaration(line : &str) -> bool {
+    line.contains("class ")
+        || line.contains("interface ")
+        || line.contains("record ")
+        || line.contains("enum ")
+}
+
+// parse a single mapping annotation line, understanding Spring's real semantics: the annotation
+// name sets the default verb, named attributes `path`/`value` carry the path(s) (including the
+// array form `{"/a", "/b"}`), and `method = RequestMethod.X` overrides the verb for `@RequestMapping`.
+// returns `None` when the annotation name is not a mapping verb we recognise.
+fn parse_mapping_line(line : &str) -> Option<ParsedMapping> {
+
+    // which attribute the characters we are currently reading belong to
+    enum SegmentKey {
+        // a positional value before any `key =`, i.e. the shorthand `@GetMapping("/x")`
+        Positional,
+        // `path = ...` or `value = ...`
+        Path,
+        // `method = RequestMethod.X`
+        Method,
+        // an attribute we do not care about (consumes, produces, ...)
+        Other,
+    }
+
+    let mut http_verb = HttpVerbs::Any;
+    let mut paths = Vec::<PathSpan>::new();
+
+    let mut paren_depth : usize = 0;
+    let mut brace_depth : usize = 0;
+
+    let mut annotation_name = String::new();
+    let mut reading_name = true;
+
+    let mut segment_key = SegmentKey::Positional;
+    let mut ident_buffer = String::new();
+    let mut method_buffer = String::new();
+
+    let mut in_string = false;
+    let mut str_buffer = String::new();
+    let mut str_start = 0;
+    let mut prev_c = '\0';
+
+    for (index, cur_c) in line.chars().enumerate() {
+        let is_escape = prev_c == '\\';
+
+        // find the mapping annotation name, tolerating leading annotations on the same line
+        // (e.g. `@Validated @GetMapping("/x")`): accumulate each `@identifier` token and keep
+        // the one that either opens parentheses or is itself a recognised bare mapping verb
+        if reading_name {
+            if cur_c == '(' {
+                if !ident_buffer.trim().is_empty() {
+                    annotation_name = ident_buffer.clone();
+                }
+                ident_buffer.clear();
+                reading_name = false;
+                paren_depth = 1;
+            } else if cur_c == '@' {
+                // start of a new annotation token; discard any non-mapping token seen so far
+                ident_buffer.clear();
+                ident_buffer.push('@');
+            } else if cur_c.is_whitespace() {
+                // a completed token with no parentheses: keep it only if it's a bare mapping verb
+                if HttpVerbs::from_annotation(ident_buffer.trim()).is_some() {
+                    annotation_name = ident_buffer.clone();
+                }
+                ident_buffer.clear();
+            } else {
+                ident_buffer.push(cur_c);
+            }
+            prev_c = cur_c;
+            continue;
+        }
+
+        // inside a string literal: collect until the unescaped closing quote
+        if in_string {
+            if cur_c == '"' && !is_escape {
+                in_string = false;
+                if matches!(segment_key, SegmentKey::Path | SegmentKey::Positional) {
+                    paths.push(PathSpan { path: str_buffer.clone(), start: str_start, len: index - str_start });
+                }
+                str_buffer.clear();
+            } else if cur_c == '\\' && !is_escape {
+                // drop the escape backslash itself
+            } else {
+                str_buffer.push(cur_c);
+            }
+            // an escaped character (including an escaped backslash) does not escape the next one
+            prev_c = if is_escape { '\0' } else { cur_c };
+            continue;
+        }
+
+        match cur_c {
+            '"' => {
+                in_string = true;
+                str_start = index + 1;
+            },
+            '{' => brace_depth += 1,
+            '}' => brace_depth = brace_depth.saturating_sub(1),
+            '(' => paren_depth += 1,
+            ')' => {
+                paren_depth = paren_depth.saturating_sub(1);
+                if paren_depth == 0 {
+                    // flush a trailing `method = ...` value before leaving the attribute list
+                    if matches!(segment_key, SegmentKey::Method) {
+                        if let Some(verb) = HttpVerbs::from_request_method(method_buffer.trim()) {
+                            http_verb = verb;
+                        }
+                    }
+                    break;
+                }
+            },
+            '=' => {
+                // the identifier just read names the attribute for the upcoming value
+                segment_key = match ident_buffer.trim() {
+                    "path" | "value" => SegmentKey::Path,
+                    "method" => SegmentKey::Method,
+                    _ => SegmentKey::Other,
+                };
+                ident_buffer.clear();
+            },
+            ',' if brace_depth == 0 => {
+                // end of a top-level attribute: resolve any pending method value, then reset
+                if matches!(segment_key, SegmentKey::Method) {
+                    if let Some(verb) = HttpVerbs::from_request_method(method_buffer.trim()) {
+                        http_verb = verb;
+                    }
+                }
+                method_buffer.clear();
+                ident_buffer.clear();
+                segment_key = SegmentKey::Positional;
+            },
+            _ => {
+                if matches!(segment_key, SegmentKey::Method) {
+                    if !cur_c.is_whitespace() {
+                        method_buffer.push(cur_c);
+                    }
+                } else if !cur_c.is_whitespace() {
+                    ident_buffer.push(cur_c);
+                }
+            }
+        }
+
+        prev_c = cur_c;
+    }
+
+    // a bare annotation that ran to the end of the line (no trailing space or '(') is still in
+    // the identifier buffer; recognise it if we never committed a name
+    if annotation_name.trim().is_empty() && HttpVerbs::from_annotation(ident_buffer.trim()).is_some() {
+        annotation_name = ident_buffer.clone();
+    }
+
+    // the annotation name provides the default verb; skip annotations we don't recognise
+    let name_verb = HttpVerbs::from_annotation(annotation_name.trim())?;
+
+    // an explicit verb annotation (@GetMapping, ...) always wins; @RequestMapping keeps
+    // whatever a `method =` attribute resolved to (ANY when none was given)
+    if !matches!(name_verb, HttpVerbs::Any) {
+        http_verb = name_verb;
+    }
+
+    // a mapping with no quoted path still yields one (empty) endpoint, as before
+    if paths.is_empty() {
+        paths.push(PathSpan { path: String::new(), start: 0, len: 0 });
+    }
+
+    Some(ParsedMapping { http_verb, paths })
+}
+
+// join a class-level base path and a method-level path with a single '/'
+fn join_paths(base : &str, path : &str) -> String {
+    if base.is_empty() {
+        return String::from(path);
+    }
+    if path.is_empty() {
+        return String::from(base);
+    }
+    format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
 }
 
 struct ControllerFileSearchResult {
@@ -103,209 +391,469 @@ struct ControllerFileSearchResult {
     request_search_results: Vec<ControllerRequestFileSearchResult>
 }
 
+// escape a string so it can be embedded inside a JSON double-quoted literal
+fn json_escape(value : &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// escape a field for CSV, quoting it when it contains a comma, quote or newline
+fn csv_escape(value : &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
 impl ControllerFileSearchResult {
     fn new(file_name : String) -> ControllerFileSearchResult {
-        ControllerFileSearchResult { file_name: file_name, request_search_results: Vec::<ControllerRequestFileSearchResult>::new() }
+        ControllerFileSearchResult { file_name, request_search_results: Vec::<ControllerRequestFileSearchResult>::new() }
     }
 }
 
 struct ControllerRequestFileSearchResult {
     http_verb : HttpVerbs,
     rest_path: String,
+    // 1-based line the annotation was found on, the full source line, and the char span of the
+    // extracted path within that line (start column and length), used to render diagnostics
+    line_number : usize,
+    line_text : String,
+    path_start : usize,
+    path_len : usize,
 }
 
 fn main() {
-    let app_args = AppArguments::new();
-    let map_dir_name = app_args.arguments.get("mapdir").expect("Expected the argument -mapdir with the location to map the directory");
+    let app_args = AppArguments::parse();
+
+    // wire the selected log level into the global logger
+    log::set_logger(&LOGGER).expect("Could not install the stderr logger");
+    log::set_max_level(app_args.loglevel.to_level_filter());
+
+    // the `list-verbs` subcommand just prints the known verbs and exits
+    if let Some(Command::ListVerbs) = app_args.command {
+        for verb in HttpVerbs::all() {
+            println!("{}", verb.as_str());
+        }
+        return;
+    }
+
+    // number of worker threads, defaults to the available parallelism of the host
+    let thread_count = match app_args.threads {
+        Some(value) => value,
+        None => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+    };
+
+    info!("Scanning '{}' with {} worker thread(s)", app_args.mapdir, thread_count);
 
     // call search_in_dir to find controllers
-    let results = search_in_dir(map_dir_name);
+    let results = search_in_dir(&app_args.mapdir, thread_count, &app_args.pattern, &app_args.include, &app_args.exclude);
+
+    info!("Found {} controller file(s)", results.len());
+
+    match app_args.format {
+        OutputFormat::Json => print_json(&results),
+        OutputFormat::Csv => print_csv(&results),
+        OutputFormat::Text => print_text(&results),
+        OutputFormat::Diagnostic => print_diagnostic(&results),
+    }
+}
 
+// the original human-readable dump: one file name per block, endpoints indented beneath it
+fn print_text(results : &[ControllerFileSearchResult]) {
     for result in results {
         println!("{}", result.file_name);
 
-        for req_result in result.request_search_results {
+        for req_result in &result.request_search_results {
             println!("\t{} {}", req_result.http_verb.as_str(), req_result.rest_path);
         }
     }
 }
 
-fn search_in_dir(dir_name : &str) -> Vec<ControllerFileSearchResult> {
+// serialize the full result tree as a JSON array of objects so the output can be piped into other tools
+fn print_json(results : &[ControllerFileSearchResult]) {
+    print!("[");
+    for (file_index, result) in results.iter().enumerate() {
+        if file_index > 0 {
+            print!(",");
+        }
+        print!("{{\"file\":\"{}\",\"requests\":[", json_escape(&result.file_name));
+        for (req_index, req_result) in result.request_search_results.iter().enumerate() {
+            if req_index > 0 {
+                print!(",");
+            }
+            print!(
+                "{{\"verb\":\"{}\",\"path\":\"{}\"}}",
+                json_escape(req_result.http_verb.as_str()),
+                json_escape(&req_result.rest_path)
+            );
+        }
+        print!("]}}");
+    }
+    println!("]");
+}
 
-    // read the directory entries
-    let read_dir = match fs::read_dir(dir_name) {
-        Ok(dirs) => dirs,
-        Err(e) => panic!("Could not map 'mapdir' directory: {}", e.to_string())
-    };
+// emit one CSV row per endpoint with a header describing the columns
+fn print_csv(results : &[ControllerFileSearchResult]) {
+    println!("file,verb,path");
+    for result in results {
+        for req_result in &result.request_search_results {
+            println!(
+                "{},{},{}",
+                csv_escape(&result.file_name),
+                csv_escape(req_result.http_verb.as_str()),
+                csv_escape(&req_result.rest_path)
+            );
+        }
+    }
+}
 
-    // file buffer maximum size (8 MB)
-    const BUFFER_SIZE : usize = 1024 * 1024 * 8;
-    let file_name_regex : Regex = Regex::new(r"^.?*(Controller\.java)$").unwrap();
-    let endpoint_regex : Regex = Regex::new(r"(@)\w+(Mapping).*").unwrap();
+// render each match like a compiler diagnostic: the file name, the 1-based line number, the
+// source line with a gutter, and a caret span underlining the exact columns of the path
+fn print_diagnostic(results : &[ControllerFileSearchResult]) {
+    for result in results {
+        for req_result in &result.request_search_results {
+            println!("{}:{}", result.file_name, req_result.line_number);
+
+            // gutter prefix, e.g. "  12 | ", reused to align the caret line beneath the source
+            let gutter = format!("  {} | ", req_result.line_number);
+            println!("{}{}", gutter, req_result.line_text);
+
+            // pad with spaces up to the path's start column, then underline its length
+            let mut underline = String::new();
+            for _ in 0..gutter.chars().count() + req_result.path_start {
+                underline.push(' ');
+            }
+            for _ in 0..req_result.path_len {
+                underline.push('^');
+            }
+            println!("{} {}", underline, req_result.http_verb.as_str());
+        }
+    }
+}
+
+fn search_in_dir(dir_name : &str, thread_count : usize, pattern : &str, include : &[String], exclude : &[String]) -> Vec<ControllerFileSearchResult> {
+
+    // walk the directory tree up front and collect every candidate file path onto the shared work queue
+    let candidate_files = collect_candidate_files(dir_name, include, exclude);
+
+    // compile the regexes once and share them with every worker, rather than per file
+    let file_name_regex = Arc::new(Regex::new(pattern).expect("Invalid --pattern regex"));
+    let endpoint_regex = Arc::new(Regex::new(r"(@)\w+(Mapping).*").unwrap());
+
+    // shared work queue the workers pop paths from, and the channel they push their results back over
+    let work_queue = Arc::new(Mutex::new(candidate_files));
+    let (result_sender, result_receiver) = mpsc::channel::<ControllerFileSearchResult>();
+
+    // fan the file work out to a fixed set of worker threads
+    let mut workers = Vec::with_capacity(thread_count.max(1));
+    for _ in 0..thread_count.max(1) {
+        let work_queue = Arc::clone(&work_queue);
+        let file_name_regex = Arc::clone(&file_name_regex);
+        let endpoint_regex = Arc::clone(&endpoint_regex);
+        let result_sender = result_sender.clone();
+        workers.push(thread::spawn(move || {
+            loop {
+                // pop the next path, releasing the lock before doing any I/O
+                let next_path = {
+                    let mut queue = work_queue.lock().unwrap();
+                    queue.pop()
+                };
+                let path = match next_path {
+                    Some(path) => path,
+                    None => break,
+                };
+                if let Some(result) = scan_file(&path, &file_name_regex, &endpoint_regex) {
+                    // the receiver is drained until every sender has been dropped, so this send never fails
+                    result_sender.send(result).unwrap();
+                }
+            }
+        }));
+    }
 
-    // store the results from the searched files
+    // drop our own sender so the channel closes once every worker is done
+    drop(result_sender);
+
+    // collect the results as the workers produce them
     let mut file_search_results = Vec::<ControllerFileSearchResult>::new();
+    for result in result_receiver {
+        file_search_results.push(result);
+    }
 
-    for dir in read_dir {
-        if let Ok(dir_entry) = dir {
+    for worker in workers {
+        worker.join().unwrap();
+    }
 
-            let dir_entry_path =  dir_entry.path();
+    // workers finish in nondeterministic order; sort by file name so the output is ordered
+    // by name (the relative order of files sharing a name still depends on thread scheduling)
+    file_search_results.sort_by(|a, b| a.file_name.cmp(&b.file_name));
 
-            // call this function recursively if the directory entry is a directory and skip to the next iter element
-            // ignore directories that starts with '.'
-            if  dir_entry_path.is_dir() && 
-                !dir_entry_path.file_name().unwrap().to_str().unwrap().starts_with('.') {
-                file_search_results.append(&mut search_in_dir(dir_entry_path.to_str().unwrap()));
+    file_search_results
+}
+
+// walk the directory tree with an ignore-aware walker, honouring any `.gitignore`/`.ignore`
+// files encountered along the way plus the `--include`/`--exclude` glob overrides, and return
+// every regular file that survives the filters.
+fn collect_candidate_files(dir_name : &str, include : &[String], exclude : &[String]) -> Vec<PathBuf> {
+
+    // build the override matcher set: bare globs whitelist, `!`-prefixed globs are excluded
+    let mut override_builder = OverrideBuilder::new(dir_name);
+    for glob in include {
+        override_builder.add(glob).expect("Invalid --include glob pattern");
+    }
+    for glob in exclude {
+        override_builder.add(&format!("!{}", glob)).expect("Invalid --exclude glob pattern");
+    }
+    let overrides = override_builder.build().expect("Could not build the glob override matcher");
+
+    // `.gitignore`/`.ignore` parsing and hidden-directory skipping are on by default;
+    // `require_git(false)` makes `.gitignore` files apply even outside a git checkout
+    let walk = WalkBuilder::new(dir_name)
+        .overrides(overrides)
+        .require_git(false)
+        .build();
+
+    let mut candidate_files = Vec::<PathBuf>::new();
+    for entry in walk {
+        // tolerate per-entry failures (e.g. a permission-denied subdirectory) instead of
+        // aborting the whole scan, mirroring the baseline's forgiving traversal
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Skipping unreadable entry: {}", e);
                 continue;
             }
+        };
 
-            // process the file if the directory entry is a file
-            if dir_entry_path.is_file() {
+        // queue regular files so a worker can scan them
+        if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            candidate_files.push(entry.into_path());
+        }
+    }
 
-                // read the file metadata
-                let file_metadata = match dir_entry.metadata() {
-                    Ok(m) => m,
-                    Err(_) => panic!("Could not read metadata from file")
-                };
+    candidate_files
+}
 
-                // check if file length is bigger than the maximum buffer size
-                if file_metadata.len() > BUFFER_SIZE as u64 {
-                    println!("Ignoring file {} because it exceeds the buffer limit of {} bytes", dir_entry_path.to_string_lossy(), BUFFER_SIZE);
-                    continue;
-                }
+// read and scan a single candidate file, returning its endpoints or `None` when the file is
+// skipped (too large, wrong name, unreadable). Runs entirely inside a worker thread.
+fn scan_file(path : &PathBuf, file_name_regex : &Regex, endpoint_regex : &Regex) -> Option<ControllerFileSearchResult> {
 
-                // ignore files that does not end with *Controller.java
-                if !file_name_regex.is_match(dir_entry.file_name().to_str().unwrap()) {
-                    continue;
-                }
+    // file buffer maximum size (8 MB)
+    const BUFFER_SIZE : usize = 1024 * 1024 * 8;
 
-                // read the file content
-                let mut file = File::open(dir_entry_path).expect("Could not open file");
-                let mut buf = vec![];
-                match file.read_to_end(&mut buf) {
-                    Err(e) => {
-                        println!("Could not read file:{}. Reason:{}", dir_entry.file_name().to_str().unwrap(), e.to_string());
-                        continue;
-                    }
-                    _ => ()
-                }
-                let file_data = String::from_utf8_lossy(&buf);
+    let file_name = path.file_name().unwrap().to_str().unwrap();
 
-                let mut file_search_result = ControllerFileSearchResult::new(String::from(dir_entry.file_name().to_str().unwrap()));
+    // read the file metadata
+    let file_metadata = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) => {
+            warn!("Could not read metadata from file:{}. Reason:{}", file_name, e);
+            return None;
+        }
+    };
 
-                // iterate over each line of the file trying to find a match to the controller endpoints
-                for line in file_data.split('\n') {
+    // check if file length is bigger than the maximum buffer size
+    if file_metadata.len() > BUFFER_SIZE as u64 {
+        // diagnostics go to the logger (stderr), never stdout, so they can't corrupt JSON/CSV output
+        warn!("Ignoring file {} because it exceeds the buffer limit of {} bytes", path.to_string_lossy(), BUFFER_SIZE);
+        return None;
+    }
 
+    // ignore files whose name does not match the file name pattern (`*Controller.java` by default)
+    if !file_name_regex.is_match(file_name) {
+        return None;
+    }
 
-                    // check if the current line matches and endpoint declaration @[HttpVerb]Request
-                    if endpoint_regex.is_match(line) && line.trim().starts_with('@') {
+    // read the file content
+    let mut file = match File::open(path) {
+        Ok(f) => f,
+        Err(e) => {
+            warn!("Could not open file:{}. Reason:{}", file_name, e);
+            return None;
+        }
+    };
+    let mut buf = vec![];
+    if let Err(e) = file.read_to_end(&mut buf) {
+        warn!("Could not read file:{}. Reason:{}", file_name, e);
+        return None;
+    }
+    let file_data = String::from_utf8_lossy(&buf);
 
-                        enum Contexts {
-                            AnnotationName,
-                            AnnotationAttributes,
-                            EndpointPath,
-                            EOC,
-                        }
+    let mut file_search_result = ControllerFileSearchResult::new(String::from(file_name));
 
-                        let mut cur_context = Contexts::AnnotationName;
-                        let mut str_buffer = String::new();
-                        let mut prev_c = '\0';
-
-                        let mut endpoint_path = String::new();
-                        let mut http_verb = HttpVerbs::Any;
-
-                        for (index, cur_c) in line.chars().enumerate() {
-
-                            // flag that indicates that the current character is escaped
-                            let is_escape = prev_c == '\\';
-
-                            match cur_context {
-                                Contexts::AnnotationName => {
-                                    // end of context
-                                    if cur_c == '(' || index == line.len() - 1 {
-                                        if str_buffer == "@RequestMapping" {
-                                            http_verb = HttpVerbs::Any;
-                                        }
-                                        else if str_buffer == "@DeleteMapping" {
-                                            http_verb = HttpVerbs::Delete;
-                                        }
-                                        else if str_buffer == "@GetMapping" {
-                                            http_verb = HttpVerbs::Get;
-                                        }
-                                        else if str_buffer == "@HeadMapping" {
-                                            http_verb = HttpVerbs::Head;
-                                        }
-                                        else if str_buffer == "@OptionsMapping" {
-                                            http_verb = HttpVerbs::Options;
-                                        }
-                                        else if str_buffer == "@PatchMapping" {
-                                            http_verb = HttpVerbs::Patch;
-                                        }
-                                        else if str_buffer == "@PostMapping" {
-                                            http_verb = HttpVerbs::Post;
-                                        }
-                                        else if str_buffer == "@PutMapping" {
-                                            http_verb = HttpVerbs::Put;
-                                        }
-                                        else {
-                                            panic!("Unknown http verb annotation found:{}", str_buffer);
-                                        }
-
-                                        // clear the buffer and go to the next context
-                                        str_buffer.clear();
-                                        
-                                        // 
-                                        if cur_c == '(' {
-                                            cur_context = Contexts::AnnotationAttributes
-                                        }
-                                    }
-                                    // on context
-                                    else {
-                                        if !cur_c.is_whitespace() {
-                                            str_buffer.push(cur_c);
-                                        }
-                                    }
-
-                                },
-                                Contexts::AnnotationAttributes => {
-                                    if cur_c == '"' {
-                                        cur_context = Contexts::EndpointPath;
-                                    }
-                                },
-                                Contexts::EndpointPath => {
-                                    // end of context
-                                    if cur_c == '"' && !is_escape {
-                                        endpoint_path = String::from(&str_buffer);
-                                        str_buffer.clear();
-                                        cur_context = Contexts::EOC;
-                                    }
-                                    // on context
-                                    else {
-                                        // ignore '\' only if not on escape character
-                                        if cur_c == '\\' && !is_escape {
-                                            continue;
-                                        }
-                                        str_buffer.push(cur_c);
-                                    }
-                                },
-                                Contexts::EOC => ()
-                            }
-                            // store the current charcter as previous before going to the next iter()
-                            prev_c = cur_c;
-                        }
+    let lines : Vec<&str> = file_data.split('\n').collect();
+
+    // a class-level `@RequestMapping("/api")` base path is prepended to every method mapping;
+    // detect it as the mapping annotation whose next code line declares the class
+    let mut base_path = String::new();
+    let mut class_level_line : Option<usize> = None;
+    for (line_index, line) in lines.iter().enumerate() {
+        if !is_mapping_line(line, endpoint_regex) {
+            continue;
+        }
 
-                        // on this context, the request line process is finished
-                        file_search_result.request_search_results.push(
-                            ControllerRequestFileSearchResult { http_verb: http_verb,  rest_path: endpoint_path }
-                        )
+        // look ahead past blank/annotation/comment lines to the next substantive declaration
+        for next in lines.iter().skip(line_index + 1) {
+            let trimmed = next.trim();
+            if trimmed.is_empty()
+                || trimmed.starts_with('@')
+                || trimmed.starts_with("//")
+                || trimmed.starts_with('*')
+                || trimmed.starts_with("/*") {
+                continue;
+            }
+            if is_type_declaration(trimmed) {
+                // only a recognised mapping annotation can act as the class-level base path
+                if let Some(parsed) = parse_mapping_line(line) {
+                    if let Some(first) = parsed.paths.first() {
+                        base_path = first.path.clone();
                     }
-                }                
-            
-                // on this context the line by line process is finished
-                file_search_results.push(file_search_result);
+                    class_level_line = Some(line_index);
+                }
             }
+            break;
+        }
+
+        if class_level_line.is_some() {
+            break;
         }
     }
 
-    file_search_results
+    // iterate over each line of the file trying to find a match to the controller endpoints
+    for (line_index, line) in lines.iter().enumerate() {
+
+        // the class-level mapping is the base path, not an endpoint of its own
+        if Some(line_index) == class_level_line {
+            continue;
+        }
+
+        // check if the current line matches an endpoint declaration @[HttpVerb]Mapping
+        if is_mapping_line(line, endpoint_regex) {
+
+            // skip annotations whose name isn't a mapping verb we recognise (e.g. @MessageMapping)
+            let parsed = match parse_mapping_line(line) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+
+            // emit one result per path, prepending the class-level base path to each
+            for span in parsed.paths {
+                file_search_result.request_search_results.push(
+                    ControllerRequestFileSearchResult {
+                        http_verb: parsed.http_verb,
+                        rest_path: join_paths(&base_path, &span.path),
+                        line_number: line_index + 1,
+                        line_text: String::from(*line),
+                        path_start: span.start,
+                        path_len: span.len,
+                    }
+                )
+            }
+        }
+    }
+
+    // on this context the line by line process is finished
+    Some(file_search_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // collect just the path strings from a parsed mapping for terse assertions
+    fn paths_of(line : &str) -> Vec<String> {
+        parse_mapping_line(line).unwrap().paths.into_iter().map(|span| span.path).collect()
+    }
+
+    #[test]
+    fn shorthand_value_is_the_path() {
+        let parsed = parse_mapping_line(r#"    @GetMapping("/users")"#).unwrap();
+        assert_eq!(parsed.http_verb.as_str(), "GET");
+        assert_eq!(parsed.paths.into_iter().map(|s| s.path).collect::<Vec<_>>(), vec!["/users"]);
+    }
+
+    #[test]
+    fn named_path_and_value_attributes() {
+        assert_eq!(paths_of(r#"@GetMapping(path = "/a")"#), vec!["/a"]);
+        assert_eq!(paths_of(r#"@GetMapping(value = "/b")"#), vec!["/b"]);
+        // attributes we don't care about must not be mistaken for the path
+        assert_eq!(paths_of(r#"@GetMapping(value = "/c", produces = "application/json")"#), vec!["/c"]);
+    }
+
+    #[test]
+    fn method_attribute_sets_the_verb() {
+        let parsed = parse_mapping_line(r#"@RequestMapping(path = "/p", method = RequestMethod.POST)"#).unwrap();
+        assert_eq!(parsed.http_verb.as_str(), "POST");
+        assert_eq!(parsed.paths.into_iter().map(|s| s.path).collect::<Vec<_>>(), vec!["/p"]);
+    }
+
+    #[test]
+    fn request_mapping_without_method_is_any() {
+        let parsed = parse_mapping_line(r#"@RequestMapping("/x")"#).unwrap();
+        assert_eq!(parsed.http_verb.as_str(), "ANY");
+    }
+
+    #[test]
+    fn array_form_emits_one_path_each() {
+        assert_eq!(paths_of(r#"@RequestMapping(value = {"/a", "/b"})"#), vec!["/a", "/b"]);
+    }
+
+    #[test]
+    fn unknown_annotation_is_skipped() {
+        assert!(parse_mapping_line(r#"@MessageMapping("/topic")"#).is_none());
+    }
+
+    #[test]
+    fn leading_annotation_on_same_line_is_ignored() {
+        let parsed = parse_mapping_line(r#"    @Validated @GetMapping("/bar")"#).unwrap();
+        assert_eq!(parsed.http_verb.as_str(), "GET");
+        assert_eq!(parsed.paths.into_iter().map(|s| s.path).collect::<Vec<_>>(), vec!["/bar"]);
+    }
+
+    #[test]
+    fn interface_declaration_is_a_type_declaration() {
+        assert!(is_type_declaration("public interface FooController {"));
+        assert!(is_type_declaration("public class UserController {"));
+        assert!(!is_type_declaration("public void handler() {"));
+    }
+
+    #[test]
+    fn path_span_tracks_columns() {
+        // "@GetMapping("/x")" -> the literal /x sits at columns 13..15
+        let parsed = parse_mapping_line(r#"@GetMapping("/x")"#).unwrap();
+        let span = &parsed.paths[0];
+        assert_eq!(span.start, 13);
+        assert_eq!(span.len, 2);
+    }
+
+    #[test]
+    fn join_paths_uses_single_slash() {
+        assert_eq!(join_paths("/api", "/users"), "/api/users");
+        assert_eq!(join_paths("/api/", "users"), "/api/users");
+        assert_eq!(join_paths("", "/users"), "/users");
+        assert_eq!(join_paths("/api", ""), "/api");
+    }
+
+    #[test]
+    fn json_escape_escapes_quotes_and_controls() {
+        assert_eq!(json_escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(json_escape("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn csv_escape_quotes_only_when_needed() {
+        assert_eq!(csv_escape("/plain"), "/plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("a\"b"), "\"a\"\"b\"");
+    }
 }